@@ -0,0 +1,89 @@
+//! Namespaced attribute names.
+//!
+//! Attribute keys used to be plain `&'static str`s, which can't tell an SVG
+//! attribute apart from one in another namespace (`xlink:href` vs a
+//! hypothetical unprefixed `href`). A `LocalName` pairs the local part of an
+//! expanded name with its namespace, so `href` and `xlink:href` are distinct
+//! keys. Two `LocalName`s are equal when their namespaces match and their
+//! local parts compare equal as strings; the names are not interned, so this
+//! is a string comparison, not an identity comparison. Build one at compile
+//! time with the `local_name!` macro.
+
+use std::fmt;
+
+/// An XML namespace that an attribute's local name can live in.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Namespace {
+    None,
+    XLink,
+    Xml,
+}
+
+/// An attribute name, expanded with its namespace and built at compile
+/// time via `local_name!`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct LocalName {
+    namespace: Namespace,
+    local: &'static str,
+}
+
+impl LocalName {
+    pub const fn new(namespace: Namespace, local: &'static str) -> LocalName {
+        LocalName { namespace, local }
+    }
+
+    pub fn namespace(&self) -> Namespace {
+        self.namespace
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        self.local
+    }
+}
+
+impl fmt::Display for LocalName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.namespace {
+            Namespace::None => write!(f, "{}", self.local),
+            Namespace::XLink => write!(f, "xlink:{}", self.local),
+            Namespace::Xml => write!(f, "xml:{}", self.local),
+        }
+    }
+}
+
+/// Builds a `LocalName` for an (optionally namespaced) attribute at compile time.
+///
+/// ```ignore
+/// property_bag::parse_or_default(pbag, local_name!("gradientUnits"))?;
+/// property_bag::parse_or_none(pbag, local_name!(xlink: "href"))?;
+/// ```
+#[macro_export]
+macro_rules! local_name {
+    (xlink: $local:expr) => {
+        $crate::local_name::LocalName::new($crate::local_name::Namespace::XLink, $local)
+    };
+    (xml: $local:expr) => {
+        $crate::local_name::LocalName::new($crate::local_name::Namespace::Xml, $local)
+    };
+    ($local:expr) => {
+        $crate::local_name::LocalName::new($crate::local_name::Namespace::None, $local)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compares_by_namespace_and_local_name() {
+        assert_eq!(local_name!("href"), local_name!("href"));
+        assert_ne!(local_name!("href"), local_name!(xlink: "href"));
+    }
+
+    #[test]
+    fn displays_with_namespace_prefix() {
+        assert_eq!(local_name!("href").to_string(), "href");
+        assert_eq!(local_name!(xlink: "href").to_string(), "xlink:href");
+        assert_eq!(local_name!(xml: "lang").to_string(), "xml:lang");
+    }
+}
@@ -0,0 +1,88 @@
+//! `spreadMethod` values for gradients.
+
+use cssparser::Parser;
+
+use crate::error::*;
+use crate::parsers::ParseToParseError;
+
+/// How a gradient or pattern paint server repeats outside its natural bounds,
+/// per the `spreadMethod` attribute.
+///
+/// This is independent of `cairo::enums::Extend` so that the SVG model does
+/// not have to depend on the rendering backend; use the `From` impl below to
+/// get the `cairo` value at render time.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SpreadMethod {
+    Pad,
+    Reflect,
+    Repeat,
+}
+
+impl ParseToParseError for SpreadMethod {
+    fn parse_to_parse_error<'i>(parser: &mut Parser<'i, '_>) -> Result<Self, CssParseError<'i>> {
+        Ok(parse_identifiers!(
+            parser,
+            "pad" => SpreadMethod::Pad,
+            "reflect" => SpreadMethod::Reflect,
+            "repeat" => SpreadMethod::Repeat,
+        )?)
+    }
+}
+
+impl Default for SpreadMethod {
+    fn default() -> SpreadMethod {
+        SpreadMethod::Pad
+    }
+}
+
+impl From<SpreadMethod> for cairo::enums::Extend {
+    fn from(s: SpreadMethod) -> cairo::enums::Extend {
+        match s {
+            SpreadMethod::Pad => cairo::enums::Extend::Pad,
+            SpreadMethod::Reflect => cairo::enums::Extend::Reflect,
+            SpreadMethod::Repeat => cairo::enums::Extend::Repeat,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parsing_invalid_strings_yields_error() {
+        assert!(SpreadMethod::parse_str_to_parse_error("").is_err());
+        assert!(SpreadMethod::parse_str_to_parse_error("foobar").is_err());
+    }
+
+    #[test]
+    fn parses_spread_method() {
+        assert_eq!(SpreadMethod::parse_str_to_parse_error("pad"), Ok(SpreadMethod::Pad));
+        assert_eq!(
+            SpreadMethod::parse_str_to_parse_error("reflect"),
+            Ok(SpreadMethod::Reflect)
+        );
+        assert_eq!(
+            SpreadMethod::parse_str_to_parse_error("repeat"),
+            Ok(SpreadMethod::Repeat)
+        );
+    }
+
+    #[test]
+    fn has_correct_default() {
+        assert_eq!(SpreadMethod::default(), SpreadMethod::Pad);
+    }
+
+    #[test]
+    fn converts_to_cairo_extend() {
+        assert_eq!(cairo::enums::Extend::from(SpreadMethod::Pad), cairo::enums::Extend::Pad);
+        assert_eq!(
+            cairo::enums::Extend::from(SpreadMethod::Reflect),
+            cairo::enums::Extend::Reflect
+        );
+        assert_eq!(
+            cairo::enums::Extend::from(SpreadMethod::Repeat),
+            cairo::enums::Extend::Repeat
+        );
+    }
+}
@@ -1,9 +1,13 @@
+use std::ffi::CStr;
+use std::marker::PhantomData;
+
 use ::cairo;
 use ::glib::translate::*;
 use ::libc;
 
-use error::*;
-use parsers::Parse;
+use rsvg_internals::error::NodeError;
+use rsvg_internals::local_name::{LocalName, Namespace};
+use rsvg_internals::parsers::ParseToParseError;
 
 pub enum RsvgPropertyBag {}
 
@@ -11,56 +15,190 @@ extern "C" {
     fn rsvg_property_bag_lookup (pbag: *const RsvgPropertyBag, key: *const libc::c_char) -> *const libc::c_char;
     fn rsvg_property_bag_dup (pbag: *const RsvgPropertyBag) -> *mut RsvgPropertyBag;
     fn rsvg_property_bag_free (pbag: *mut RsvgPropertyBag);
+    fn rsvg_property_bag_enumerate (
+        pbag: *const RsvgPropertyBag,
+        callback: extern "C" fn (key: *const libc::c_char, value: *const libc::c_char, data: *mut libc::c_void),
+        data: *mut libc::c_void,
+    );
 }
 
-pub fn lookup (pbag: *const RsvgPropertyBag, key: &str) -> Option<String> {
+/// Collects one `(key, value)` pair into the `Vec<(&str, &str)>` pointed to by `data`.
+///
+/// Like `PropertyBag::lookup()`, an entry whose key or value isn't valid
+/// UTF-8 is skipped rather than unwrapped, since this walks attributes
+/// straight from untrusted, XML-supplied SVG data and must not panic on it.
+extern "C" fn collect_pair<'a> (key: *const libc::c_char, value: *const libc::c_char, data: *mut libc::c_void) {
     unsafe {
-        let c_value = rsvg_property_bag_lookup (pbag, key.to_glib_none ().0);
-        from_glib_none (c_value)
+        let pairs = &mut *(data as *mut Vec<(&'a str, &'a str)>);
+
+        let key = CStr::from_ptr (key).to_str ();
+        let value = CStr::from_ptr (value).to_str ();
+
+        if let (Ok (key), Ok (value)) = (key, value) {
+            pairs.push ((key, value));
+        }
     }
 }
 
-pub fn dup (pbag: *const RsvgPropertyBag) -> *mut RsvgPropertyBag {
-    unsafe {
-        rsvg_property_bag_dup (pbag)
+/// Distinguishes a `PropertyBag` that merely borrows its `RsvgPropertyBag`
+/// from one that owns a duplicate and must free it on `Drop`.
+enum Repr {
+    Borrowed (*const RsvgPropertyBag),
+    Owned (*mut RsvgPropertyBag),
+}
+
+/// A property bag of attribute name/value strings for one XML element,
+/// backed by the C side's `RsvgPropertyBag` (a `GHashTable`).
+///
+/// `lookup()` borrows its result directly from the underlying
+/// `GHashTable` strings, so looking up an attribute no longer copies
+/// it.  Most callers only need a `PropertyBag` for the duration of a
+/// single `set_atts()` call; for the rare case that needs to retain
+/// the bag past that (e.g. the `<svg>` node), use `dup()` to get an
+/// owned, `'static` copy that frees itself automatically when dropped.
+pub struct PropertyBag<'a> (Repr, PhantomData<&'a RsvgPropertyBag>);
+
+impl<'a> PropertyBag<'a> {
+    /// Wraps a property bag borrowed from the C side, without taking ownership of it.
+    pub fn new (pbag: *const RsvgPropertyBag) -> PropertyBag<'a> {
+        PropertyBag (Repr::Borrowed (pbag), PhantomData)
+    }
+
+    /// Returns an owned copy of this property bag, which frees itself when dropped.
+    pub fn dup (&self) -> PropertyBag<'static> {
+        unsafe {
+            PropertyBag (Repr::Owned (rsvg_property_bag_dup (self.ffi ())), PhantomData)
+        }
+    }
+
+    /// Returns the raw `RsvgPropertyBag` pointer, e.g. to pass along to other FFI calls.
+    pub fn ffi (&self) -> *const RsvgPropertyBag {
+        match self.0 {
+            Repr::Borrowed (p) => p,
+            Repr::Owned (p)    => p as *const RsvgPropertyBag,
+        }
+    }
+
+    /// Looks up `key` and returns its value, borrowed from the underlying `GHashTable`.
+    ///
+    /// Note that a value which is present but not valid UTF-8 is treated the
+    /// same as a missing one (`None`), since borrowing requires a `&str`; the
+    /// old owned `from_glib_none` path used to return such values lossily
+    /// instead of conflating them with "attribute not present." XML parsing
+    /// should already guarantee UTF-8 here, so this is a narrow edge case.
+    pub fn lookup (&self, key: &str) -> Option<&str> {
+        unsafe {
+            let c_value = rsvg_property_bag_lookup (self.ffi (), key.to_glib_none ().0);
+
+            if c_value.is_null () {
+                None
+            } else {
+                CStr::from_ptr (c_value).to_str ().ok ()
+            }
+        }
+    }
+
+    /// Returns an iterator over all the `(key, value)` pairs in this property bag,
+    /// borrowed from the underlying `GHashTable` just like `lookup()`.
+    pub fn iter (&self) -> PropertyBagIter {
+        let mut pairs = Vec::new ();
+
+        unsafe {
+            rsvg_property_bag_enumerate (self.ffi (), collect_pair, &mut pairs as *mut _ as *mut libc::c_void);
+        }
+
+        PropertyBagIter (pairs.into_iter ())
     }
 }
 
-pub fn free (pbag: *mut RsvgPropertyBag) {
-    unsafe {
-        rsvg_property_bag_free (pbag);
+/// Iterator over the `(key, value)` pairs of a `PropertyBag`; see `PropertyBag::iter`.
+pub struct PropertyBagIter<'a> (::std::vec::IntoIter<(&'a str, &'a str)>);
+
+impl<'a> Iterator for PropertyBagIter<'a> {
+    type Item = (&'a str, &'a str);
+
+    fn next (&mut self) -> Option<Self::Item> {
+        self.0.next ()
     }
 }
 
-pub fn parse_or_none<T> (pbag: *const RsvgPropertyBag, key: &'static str, data: <T as Parse>::Data) -> Result <Option<T>, NodeError>
-    where T: Parse<Err = AttributeError>
-{
-    let value = lookup (pbag, key);
+impl<'a> Drop for PropertyBag<'a> {
+    fn drop (&mut self) {
+        if let Repr::Owned (p) = self.0 {
+            unsafe {
+                rsvg_property_bag_free (p);
+            }
+        }
+    }
+}
+
+/// Writes `prefix` followed by `local` into `buf` without allocating, returning
+/// the qualified key as a `&str` if it fit.
+fn qualify<'b> (prefix: &str, local: &str, buf: &'b mut [u8]) -> Option<&'b str> {
+    let total = prefix.len () + local.len ();
+
+    if total > buf.len () {
+        return None;
+    }
+
+    buf[.. prefix.len ()].copy_from_slice (prefix.as_bytes ());
+    buf[prefix.len () .. total].copy_from_slice (local.as_bytes ());
 
-    match value {
-        Some (v) => {
-            T::parse (&v, data).map (|v| Some (v))
-                .map_err (|e| NodeError::attribute_error (key, e))
-        },
+    ::std::str::from_utf8 (&buf[.. total]).ok ()
+}
+
+/// Looks up a (possibly namespaced) attribute by its `LocalName`.
+///
+/// Unprefixed names are looked up directly; namespaced ones (`xlink:href`)
+/// are looked up under their qualified form, since that is how the C side
+/// stores them in the property bag. The qualified key is built on the stack
+/// to avoid an allocation on every namespaced lookup; only a pathologically
+/// long local name falls back to an owned `String`.
+fn lookup_local_name<'a> (pbag: &'a PropertyBag, key: LocalName) -> Option<&'a str> {
+    let prefix = match key.namespace () {
+        Namespace::None  => return pbag.lookup (key.as_str ()),
+        Namespace::XLink => "xlink:",
+        Namespace::Xml   => "xml:",
+    };
+
+    let mut buf = [0u8; 32];
 
-        None => Ok (None)
+    match qualify (prefix, key.as_str (), &mut buf) {
+        Some (qualified) => pbag.lookup (qualified),
+        None              => pbag.lookup (&key.to_string ())
     }
 }
 
-pub fn parse_or_default<T> (pbag: *const RsvgPropertyBag, key: &'static str, data: <T as Parse>::Data) -> Result <T, NodeError>
-    where T: Default + Parse<Err = AttributeError> + Copy
+/// Parses `s` as a `T`, requiring that it consume the whole value (so that
+/// e.g. `"userSpaceOnUse garbage"` is rejected instead of matching a prefix).
+fn parse_value<T> (key: LocalName, s: &str) -> Result <T, NodeError>
+    where T: ParseToParseError
 {
-    parse_or_value (pbag, key, data, T::default ())
+    T::parse_str_to_parse_error (s)
+        .map_err (|e| NodeError::parse_error (&key.to_string (), e))
 }
 
-pub fn parse_or_value<T> (pbag: *const RsvgPropertyBag, key: &'static str, data: <T as Parse>::Data, value: T) -> Result <T, NodeError>
-    where T: Default + Parse<Err = AttributeError> + Copy
+/// Parses the attribute named by `key`, e.g. `parse_or_none(pbag, local_name!("gradientUnits"))?`.
+pub fn parse_or_none<T> (pbag: &PropertyBag, key: LocalName) -> Result <Option<T>, NodeError>
+    where T: ParseToParseError
 {
-    let r = parse_or_none::<T> (pbag, key, data);
+    match lookup_local_name (pbag, key) {
+        Some (v) => parse_value::<T> (key, v).map (Some),
+        None     => Ok (None)
+    }
+}
 
-    match r {
-        Ok (Some (v)) => Ok (v),
-        Ok (None)     => Ok (value),
-        Err (e)       => Err (e)
+pub fn parse_or_default<T> (pbag: &PropertyBag, key: LocalName) -> Result <T, NodeError>
+    where T: Default + ParseToParseError
+{
+    parse_or_value (pbag, key, T::default ())
+}
+
+pub fn parse_or_value<T> (pbag: &PropertyBag, key: LocalName, value: T) -> Result <T, NodeError>
+    where T: ParseToParseError
+{
+    match parse_or_none::<T> (pbag, key)? {
+        Some (v) => Ok (v),
+        None     => Ok (value)
     }
 }